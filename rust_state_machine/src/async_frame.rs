@@ -14,6 +14,87 @@ impl FrameBlocker {
     pub async fn yield_control(&self) {
         YieldLater::new(self.waker.clone()).await;
     }
+    /// Parks until `PollingPool::notify(event)` is called, instead of waking on every frame.
+    pub async fn wait_for_event(&self, event: &EventHandle) {
+        EventFuture::new(event.state.clone()).await;
+    }
+}
+
+/// One registration of a `FrameBlocker::wait_for_event` call. Identity (not just "has this
+/// future been polled before") is what lets `EventFuture::poll` tell "I was actually notified"
+/// apart from "some unrelated waiter on the same task-level waker got polled again".
+struct Waiter {
+    notified: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+#[derive(Default)]
+struct EventState {
+    waiters: Vec<Rc<Waiter>>,
+}
+
+/// A cheap, cloneable handle to a named wakeup event. `PollingPool::notify` wakes only the
+/// blockers currently parked on this handle via `FrameBlocker::wait_for_event`, instead of
+/// `wake_children`'s wake-everyone sweep.
+#[derive(Clone)]
+pub struct EventHandle {
+    state: Rc<RefCell<EventState>>,
+}
+
+struct EventFuture {
+    state: Rc<RefCell<EventState>>,
+    waiter: RefCell<Option<Rc<Waiter>>>,
+}
+impl EventFuture {
+    fn new(state: Rc<RefCell<EventState>>) -> Self {
+        Self {
+            state,
+            waiter: RefCell::new(None),
+        }
+    }
+}
+impl Future for EventFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.waiter.borrow_mut();
+        match &*slot {
+            None => {
+                let waiter = Rc::new(Waiter {
+                    notified: Cell::new(false),
+                    waker: RefCell::new(Some(cx.waker().clone())),
+                });
+                self.state.borrow_mut().waiters.push(waiter.clone());
+                *slot = Some(waiter);
+                Poll::Pending
+            }
+            Some(waiter) => {
+                if waiter.notified.get() {
+                    let waiter = waiter.clone();
+                    self.state
+                        .borrow_mut()
+                        .waiters
+                        .retain(|w| !Rc::ptr_eq(w, &waiter));
+                    Poll::Ready(())
+                } else {
+                    *waiter.waker.borrow_mut() = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+}
+impl Drop for EventFuture {
+    /// Drops (e.g. a losing `select` branch) never see a final `Ready` poll, so this is the
+    /// only place a registration that was never notified gets removed from `EventState.waiters`.
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.borrow_mut().take() {
+            self.state
+                .borrow_mut()
+                .waiters
+                .retain(|w| !Rc::ptr_eq(w, &waiter));
+        }
+    }
 }
 
 /// A pool of children that can be woken up in a async/RMS hybrid environment.
@@ -27,10 +108,31 @@ impl PollingPool {
         self.children.push(waker.clone());
         FrameBlocker { waker }
     }
-    pub fn wake_children(&self) {
+    /// Wakes every registered `FrameBlocker` that is currently parked, returning how many were
+    /// woken so callers can tell whether a frame actually made progress.
+    pub fn wake_children(&self) -> usize {
+        let mut woken = 0;
         for child in &self.children {
             if let Some(waker) = child.borrow_mut().take() {
                 waker.wake_by_ref();
+                woken += 1;
+            }
+        }
+        woken
+    }
+    /// Creates a new event handle. Tasks park on it with `FrameBlocker::wait_for_event` and are
+    /// woken only by a matching `notify`, never by `wake_children`'s frame-tick sweep.
+    pub fn create_event(&self) -> EventHandle {
+        EventHandle {
+            state: Rc::new(RefCell::new(EventState::default())),
+        }
+    }
+    /// Wakes only the blockers currently parked on `event`, leaving every other blocker asleep.
+    pub fn notify(&self, event: &EventHandle) {
+        for waiter in event.state.borrow_mut().waiters.drain(..) {
+            waiter.notified.set(true);
+            if let Some(waker) = waiter.waker.borrow_mut().take() {
+                waker.wake();
             }
         }
     }
@@ -64,6 +166,108 @@ impl Future for YieldLater {
     }
 }
 
+/// A self-contained single-threaded executor for driving `PollingPool`-based async code in tests.
+#[cfg(test)]
+pub mod testing {
+    use std::future::Future;
+    use std::time::Duration;
+
+    use futures::executor::{LocalPool, LocalSpawner};
+    use futures::task::LocalSpawnExt;
+
+    use super::{EventHandle, PollingPool};
+    use crate::sync::tests::MockClock;
+
+    pub struct MockExecutor {
+        pool: LocalPool,
+        spawner: LocalSpawner,
+        poll_pool: PollingPool,
+        clock: MockClock,
+    }
+    impl MockExecutor {
+        pub fn new() -> Self {
+            let pool = LocalPool::new();
+            let spawner = pool.spawner();
+            Self {
+                pool,
+                spawner,
+                poll_pool: PollingPool::default(),
+                clock: MockClock::new(),
+            }
+        }
+        /// The virtual clock shared by every timer this executor's tasks were built with.
+        pub fn clock(&self) -> MockClock {
+            self.clock.clone()
+        }
+        pub fn new_blocker(&mut self) -> super::FrameBlocker {
+            self.poll_pool.new_blocker()
+        }
+        /// A handle tasks can park on with `FrameBlocker::wait_for_event` and a caller who
+        /// knows exactly which external condition just changed can wake with `notify` below,
+        /// instead of `step`'s blanket wake-everyone sweep.
+        pub fn new_event(&mut self) -> EventHandle {
+            self.poll_pool.create_event()
+        }
+        pub fn spawn(&self, fut: impl Future<Output = ()> + 'static) {
+            self.spawner
+                .spawn_local(fut)
+                .expect("failed to spawn future onto MockExecutor");
+        }
+        /// One wake-and-poll cycle: wakes every parked blocker, then drives the executor to its
+        /// next stall point.
+        pub fn step(&mut self) {
+            self.poll_pool.wake_children();
+            self.pool.run_until_stalled();
+        }
+        /// Drives the executor until nothing is left to make progress on without a further
+        /// external stimulus (IO change, timer advance, ...).
+        ///
+        /// Loops instead of a single `step()` because a task can hop from one blocker to a
+        /// different one within a single pass; it stops once a pass parks nothing beyond what
+        /// was already parked going in.
+        pub fn run_until_idle(&mut self) {
+            let mut parked = self.parked_blockers();
+            loop {
+                self.step();
+                let now_parked = self.parked_blockers();
+                if now_parked.iter().all(|p| parked.contains(p)) {
+                    break;
+                }
+                parked = now_parked;
+            }
+        }
+        /// Indices of blockers currently parked (i.e. holding a registered waker), used by
+        /// `run_until_idle` to tell "re-parked on what it already had" from "parked on
+        /// something new".
+        fn parked_blockers(&self) -> Vec<usize> {
+            self.poll_pool
+                .children
+                .iter()
+                .enumerate()
+                .filter(|(_, child)| child.borrow().is_some())
+                .map(|(i, _)| i)
+                .collect()
+        }
+        /// Moves the virtual clock forward and settles the executor in one call.
+        pub fn advance(&mut self, duration: Duration) {
+            self.clock.advance(duration);
+            self.run_until_idle();
+        }
+        /// Wakes only the tasks parked on `event` via `notify`, then settles the executor.
+        /// Use this instead of `run_until_idle` when the caller knows precisely which
+        /// condition just changed, so unrelated tasks aren't spuriously re-polled.
+        pub fn notify(&mut self, event: &EventHandle) {
+            self.poll_pool.notify(event);
+            self.pool.run_until_stalled();
+        }
+    }
+    impl Default for MockExecutor {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,30 +316,101 @@ mod tests {
         };
 
         // Pre conditions, async hasn't actually run yet
-        assert_eq!(exited.borrow().clone(), false);
-        assert_eq!(started.borrow().clone(), false);
+        assert!(!*exited.borrow());
+        assert!(!*started.borrow());
         assert_eq!(loop_count.borrow().clone(), 0);
 
         // Run one frame, it should start and be blocked
         frame();
-        assert_eq!(started.borrow().clone(), true);
-        assert_eq!(exited.borrow().clone(), false);
+        assert!(*started.borrow());
+        assert!(!*exited.borrow());
         assert_eq!(poll_count.borrow().clone(), 1);
         assert_eq!(loop_count.borrow().clone(), 0);
 
         // Another frame, counts increment
         frame();
-        assert_eq!(started.borrow().clone(), true);
-        assert_eq!(exited.borrow().clone(), false);
+        assert!(*started.borrow());
+        assert!(!*exited.borrow());
         assert_eq!(poll_count.borrow().clone(), 2);
         assert_eq!(loop_count.borrow().clone(), 1);
 
         // Set the value to true, it should exit
         *value.borrow_mut() = true;
         frame();
-        assert_eq!(started.borrow().clone(), true);
-        assert_eq!(exited.borrow().clone(), true);
+        assert!(*started.borrow());
+        assert!(*exited.borrow());
         assert_eq!(poll_count.borrow().clone(), 2);
         assert_eq!(loop_count.borrow().clone(), 2);
     }
+
+    #[test]
+    fn run_until_idle_drives_a_task_across_distinct_blockers_in_one_call() {
+        use super::testing::MockExecutor;
+
+        let mut exec = MockExecutor::new();
+        let b1 = exec.new_blocker();
+        let b2 = exec.new_blocker();
+        let stage = Rc::new(Cell::new(0));
+
+        {
+            let stage = stage.clone();
+            exec.spawn(async move {
+                b1.yield_control().await;
+                stage.set(1);
+                b2.yield_control().await;
+                stage.set(2);
+            });
+        }
+
+        exec.run_until_idle();
+        assert_eq!(stage.get(), 2);
+    }
+
+    #[test]
+    fn notify_only_wakes_blockers_parked_on_that_event() {
+        let mut poll_pool = PollingPool::default();
+        let event = poll_pool.create_event();
+        let event_blocker = poll_pool.new_blocker();
+        let frame_blocker = poll_pool.new_blocker();
+
+        let event_woken = Rc::new(RefCell::new(false));
+        let frame_woken = Rc::new(RefCell::new(false));
+
+        let mut pool = LocalPool::new();
+        {
+            let event = event.clone();
+            let event_woken = event_woken.clone();
+            pool.spawner()
+                .spawn_local(async move {
+                    event_blocker.wait_for_event(&event).await;
+                    *event_woken.borrow_mut() = true;
+                })
+                .expect("Failed to spawn event waiter");
+        }
+        {
+            let frame_woken = frame_woken.clone();
+            pool.spawner()
+                .spawn_local(async move {
+                    frame_blocker.yield_control().await;
+                    *frame_woken.borrow_mut() = true;
+                })
+                .expect("Failed to spawn frame waiter");
+        }
+
+        // Register both tasks' wakers.
+        pool.run_until_stalled();
+        assert!(!*event_woken.borrow());
+        assert!(!*frame_woken.borrow());
+
+        // wake_children should only wake the plain yield_control waiter, not the event waiter.
+        poll_pool.wake_children();
+        pool.run_until_stalled();
+        assert!(!*event_woken.borrow());
+        assert!(*frame_woken.borrow());
+
+        // notify should wake the event waiter even though wake_children never touches it.
+        poll_pool.notify(&event);
+        pool.run_until_stalled();
+        assert!(*event_woken.borrow());
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
-    first_to_complete_or_err, wait_for_one_to_complete, AsyncIO, AsyncTimer, FirstOrSecond, Light,
-    TimerOrButton,
+    first_to_complete_or_err, wait_for_one_to_complete, AsyncIO, AsyncTimer, Interval, Light,
+    TimeoutExt, TimerOrButton,
 };
 
 /// The entry point for the flashing behavior of a light when a button is pressed.
@@ -9,17 +9,22 @@ use crate::{
 ///
 /// Business logic says to wait for the button to be pressed, then flash the light
 /// until the button is released.
+///
+/// `timer` drives the blink and must be a `Repeated`-mode timer, since it is wrapped in an
+/// `Interval` and ticked once per blink; it is reset by hand only when a new flash sequence
+/// starts, never between blinks.
 pub async fn start(
     mut io: impl AsyncIO,
-    mut timer: impl AsyncTimer,
+    timer: impl AsyncTimer,
     mut transition_timer: impl AsyncTimer,
 ) -> Result<(), &'static str> {
     // initial light state is off.
     io.set_light(Light::Off);
+    let mut interval = Interval::new(timer);
 
     loop {
         io.wait_until_button_pressed().await;
-        flash_until_button_released(&mut io, &mut timer, &mut transition_timer).await?;
+        flash_until_button_released(&mut io, &mut interval, &mut transition_timer).await?;
     }
 }
 
@@ -42,11 +47,11 @@ pub async fn monitor_voltage_transition(
     expected_reading: bool,
 ) -> &'static str {
     // Wait until the reading goes to the expected value or the timer expires
-    if let FirstOrSecond::Second(_) = wait_for_one_to_complete(
-        io.wait_until_voltage_is(expected_reading),
-        transition_timer.wait_expired(),
-    )
-    .await
+    if io
+        .wait_until_voltage_is(expected_reading)
+        .timeout_after(transition_timer)
+        .await
+        .is_err()
     {
         return "Timer expired before voltage transition";
     }
@@ -63,7 +68,7 @@ pub async fn monitor_voltage_transition(
 /// at any time, this flashing behavior will stop.
 async fn flash_until_button_released(
     io: &mut impl AsyncIO,
-    timer: &mut impl AsyncTimer,
+    interval: &mut Interval<impl AsyncTimer>,
     transition_timer: &mut impl AsyncTimer,
 ) -> Result<(), &'static str> {
     // Setup our initial state of the light being on and the timer being reset
@@ -71,23 +76,22 @@ async fn flash_until_button_released(
     let mut light_state = Light::On;
     // Turn the light on
     io.set_light(light_state);
-    // Reset the timer so we get a full blink
-    timer.reset();
+    // Resync the interval so the first blink gets a full period starting now.
+    interval.reset();
     transition_timer.reset();
 
-    // Loop until the timer expires or the button is released.
-    // Keep looping if the thing that happened was the timer expiring.
+    // Loop until the interval ticks or the button is released.
+    // Keep looping if the thing that happened was the interval ticking.
     while TimerOrButton::Timer
         == first_to_complete_or_err(
             io.wait_for_released(), // Good, if the button is released, we're done
-            timer.wait_expired(),   // Good, if the timer expires, we need to flip the light
+            interval.tick(), // Good, if the interval ticks, we need to flip the light
             monitor_voltage_transition(io, transition_timer, true), // Bad, if the voltage transitions away from the expected reading
         )
         .await?
         .into()
     {
-        // Inside the loop the timer expired, reset timer, flip light state, and set light
-        timer.reset();
+        // Inside the loop the interval ticked (it re-arms itself), flip light state, and set light
         transition_timer.reset();
         light_state = light_state.toggle();
         io.set_light(light_state);
@@ -110,12 +114,13 @@ async fn timer_expired_or_button_released(
 
 #[cfg(test)]
 mod tests {
-    use futures::executor::LocalPool;
-    use futures::task::LocalSpawnExt;
     use std::cell::Cell;
+    use std::time::Duration;
     use std::{cell::RefCell, rc::Rc};
 
-    use crate::sync::tests::{MockIO, MockTimer};
+    use crate::async_frame::testing::MockExecutor;
+    use crate::sync::tests::MockIO;
+    use crate::sync::{SysTimer, TimerMode};
     use crate::{PollingAsyncIO, PollingAsyncTimer};
 
     use super::*;
@@ -125,165 +130,134 @@ mod tests {
         // A bit of setup to rig up the mock IO and timer to work in this async environment
         let light = Rc::new(RefCell::new(Light::Off));
 
-        let mut pool_poll = crate::async_frame::PollingPool::default();
+        let mut exec = MockExecutor::new();
         let button_pressed = Rc::new(RefCell::new(false));
         let voltage = Rc::new(RefCell::new(true));
+        let button_event = exec.new_event();
         let io = PollingAsyncIO {
             io: MockIO::new(button_pressed.clone(), light.clone(), voltage),
-            blocker: pool_poll.new_blocker(),
+            blocker: exec.new_blocker(),
+            button_event: button_event.clone(),
+            voltage_event: exec.new_event(),
         };
 
-        let time_expired = Rc::new(RefCell::new(false));
-        let transition_time_expired = Rc::new(RefCell::new(false));
+        let clock = exec.clock();
+        // The blink timer re-arms itself every period; the transition timer is one-shot per blink.
         let timer = PollingAsyncTimer {
-            timer: MockTimer::new(time_expired.clone()),
-            blocker: pool_poll.new_blocker(),
+            timer: SysTimer::new(clock.clone(), 1.0, TimerMode::Repeated),
+            blocker: exec.new_blocker(),
         };
         let transition_timer = PollingAsyncTimer {
-            timer: MockTimer::new(transition_time_expired.clone()),
-            blocker: pool_poll.new_blocker(),
+            timer: SysTimer::new(clock.clone(), 1.0, TimerMode::SingleShot),
+            blocker: exec.new_blocker(),
         };
 
-        let mut pool = LocalPool::new();
         let run_error = Rc::new(Cell::new(None));
         {
             let run_error = run_error.clone();
-            pool.spawner()
-                .spawn_local(async move {
-                    if let Err(e) = start(io, timer, transition_timer).await {
-                        run_error.replace(Some(e));
-                    }
-                    ()
-                })
-                .expect("Failed to spawn start");
+            exec.spawn(async move {
+                if let Err(e) = start(io, timer, transition_timer).await {
+                    run_error.replace(Some(e));
+                }
+            });
         }
 
-        let mut frame = move || {
-            pool_poll.wake_children();
-            pool.run_until_stalled();
-        };
-
         // Should be off
-        for _ in 0..10 {
-            frame();
-            assert_eq!(*light.borrow(), Light::Off);
-        }
+        exec.run_until_idle();
+        assert_eq!(*light.borrow(), Light::Off);
 
         // simulate button press
         button_pressed.replace(true);
+        exec.notify(&button_event);
+        assert_eq!(*light.borrow(), Light::On);
 
+        // Simulate a timer expiration by advancing the virtual clock past the 1 second blink period
+        exec.advance(Duration::from_secs_f64(1.1));
         assert_eq!(*light.borrow(), Light::Off);
-        for i in 0..10 {
-            frame();
-            assert_eq!(*light.borrow(), Light::On, "Failed on iteration {}", i);
-        }
-
-        // Simulate a timer expiration
-        *time_expired.borrow_mut() = true;
-
-        // Should switch to off
-        for _ in 0..10 {
-            frame();
-            assert_eq!(*light.borrow(), Light::Off);
-        }
-        assert_eq!(time_expired.borrow().clone(), false);
 
         // And back on again
-        *time_expired.borrow_mut() = true;
-        for _ in 0..10 {
-            frame();
-            assert_eq!(*light.borrow(), Light::On);
-        }
+        exec.advance(Duration::from_secs_f64(1.1));
+        assert_eq!(*light.borrow(), Light::On);
 
         // And release the button, should go off for good
         button_pressed.replace(false);
-        for _ in 0..10 {
-            frame();
-            assert_eq!(*light.borrow(), Light::Off);
-        }
+        exec.notify(&button_event);
+        assert_eq!(*light.borrow(), Light::Off);
     }
 
     #[test]
     fn test_voltage_monitor() {
-        let timer_expired = Rc::new(RefCell::new(false));
         let voltage_value = Rc::new(RefCell::new(true));
         let voltage_errored = Rc::new(Cell::new(None));
 
         let reset = || {
-            timer_expired.replace(false);
             voltage_value.replace(true);
             voltage_errored.replace(None);
 
-            let mut pool_poll = crate::async_frame::PollingPool::default();
+            let mut exec = MockExecutor::new();
             let timer = PollingAsyncTimer {
-                timer: MockTimer::new(timer_expired.clone()),
-                blocker: pool_poll.new_blocker(),
+                timer: SysTimer::new(exec.clock(), 1.0, TimerMode::SingleShot),
+                blocker: exec.new_blocker(),
             };
+            let voltage_event = exec.new_event();
             let io = PollingAsyncIO {
                 io: MockIO::new(
                     Rc::new(RefCell::new(false)),
                     Rc::new(RefCell::new(Light::Off)),
                     voltage_value.clone(),
                 ),
-                blocker: pool_poll.new_blocker(),
+                blocker: exec.new_blocker(),
+                button_event: exec.new_event(),
+                voltage_event: voltage_event.clone(),
             };
 
-            let mut pool = LocalPool::new();
             let voltage_errored = voltage_errored.clone();
-            pool.spawner()
-                .spawn_local(async move {
-                    let e = monitor_voltage_transition(&io, &timer, true).await;
-                    voltage_errored.replace(Some(e));
-                    ()
-                })
-                .expect("must spawn");
-
-            move || {
-                pool_poll.wake_children();
-                pool.run_until_stalled();
-            }
+            exec.spawn(async move {
+                let e = monitor_voltage_transition(&io, &timer, true).await;
+                voltage_errored.replace(Some(e));
+            });
+
+            (exec, voltage_event)
         };
-        let mut frame = reset();
+        let (mut exec, voltage_event) = reset();
 
         // Start low, should wait for the transition
         voltage_value.replace(false);
-        frame();
+        exec.notify(&voltage_event);
         assert_eq!(voltage_errored.get(), None);
 
         // expire timer and should fail.
-        *timer_expired.borrow_mut() = true;
-        frame();
+        exec.advance(Duration::from_secs_f64(1.1));
         assert_eq!(
             voltage_errored.get(),
             Some("Timer expired before voltage transition")
         );
 
-        let mut frame = reset();
-        frame();
+        let (mut exec, voltage_event) = reset();
+        exec.run_until_idle();
         assert_eq!(voltage_errored.get(), None);
 
         // Change our voltage, and internally should transition to the waiting
         // for the voltage to transition back
-        frame();
+        exec.run_until_idle();
         assert_eq!(voltage_errored.get(), None);
 
         // Now drop voltage and see that it fails
         voltage_value.replace(false);
-        frame();
+        exec.notify(&voltage_event);
         assert_eq!(
             voltage_errored.get(),
             Some("Voltage transitioned away from expected reading after transition.")
         );
 
         // Just for fun, if the timer expires after transition, aok
-        let mut frame = reset();
+        let (mut exec, voltage_event) = reset();
         voltage_value.replace(true);
-        frame();
+        exec.notify(&voltage_event);
         assert_eq!(voltage_errored.get(), None);
 
         // Should have transitioned to the waiting for the voltage to transition back
-        *timer_expired.borrow_mut() = true;
-        frame();
+        exec.advance(Duration::from_secs_f64(1.1));
         assert_eq!(voltage_errored.get(), None);
     }
 }
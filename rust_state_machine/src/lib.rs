@@ -1,9 +1,14 @@
-use async_frame::FrameBlocker;
+use std::cell::Cell;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_frame::{EventHandle, FrameBlocker};
 use futures::Future;
 
 pub mod async_frame;
 pub mod asynchronous;
 pub mod sync;
+pub mod wheel;
 
 /// Representation of a light state being on or off.
 #[derive(PartialEq, Default, Debug, Clone, Copy)]
@@ -21,16 +26,6 @@ impl Light {
         }
     }
 }
-pub trait Timer {
-    fn reset(&mut self);
-    fn expired(&self) -> bool;
-}
-pub trait TimerFactory<T> {
-    fn new_timer(&self, timeout: f64) -> T
-    where
-        T: Timer;
-}
-
 // Should really separate this out into two traits, one for an abstract button and one for an abstract light.
 pub trait IO {
     /// Returns true if the button is currently pressed.
@@ -78,6 +73,38 @@ where
     }
 }
 
+/// Wait for both futures to complete and return both outputs once the slower one finishes.
+/// This is a wrapper around the join function from the futures crate for the common case of
+/// two futures that both need to complete before moving on, rather than racing them.
+pub async fn wait_for_all_to_complete<Fut1, Fut2, Out1, Out2>(fut1: Fut1, fut2: Fut2) -> (Out1, Out2)
+where
+    Fut1: Future<Output = Out1>,
+    Fut2: Future<Output = Out2>,
+{
+    futures::future::join(fut1, fut2).await
+}
+
+/// Wait for every future in `futures` to complete and return their outputs in the same order.
+/// Each child is polled once per frame through the usual `FrameBlocker`/`PollingPool` model,
+/// same as `wait_for_all_to_complete` but for a dynamic number of futures of the same type.
+pub async fn wait_for_all<Fut, Out>(futures: Vec<Fut>) -> Vec<Out>
+where
+    Fut: Future<Output = Out>,
+{
+    futures::future::join_all(futures).await
+}
+
+/// Polls every future in `futures` once per frame and resolves as soon as any one yields
+/// `Err`, dropping the rest immediately instead of waiting for them to finish. If none ever
+/// error, resolves to `Ok` with every output in the original order once the slowest future
+/// completes. The `try_join` analogue to `wait_for_all`.
+pub async fn try_wait_all<Fut, Out, Err>(futures: Vec<Fut>) -> Result<Vec<Out>, Err>
+where
+    Fut: Future<Output = Result<Out, Err>>,
+{
+    futures::future::try_join_all(futures).await
+}
+
 /// Given two futures, wait on both and return an Ok result if the good future completes first, or an Err result if the error future completes first.
 ///
 /// This simply converts a FirstOrSecond into an Result where First is Ok and Second is Err.
@@ -122,6 +149,54 @@ where
     }
 }
 
+thread_local! {
+    /// Advances by one on every `select_any` call so repeated calls don't all start polling
+    /// the same branch first. Thread-local rather than an `AtomicUsize` since this crate's
+    /// cooperative polling model is single-threaded throughout.
+    static SELECT_ANY_OFFSET: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Waits for whichever future in `futures` completes first, returning its index and output and
+/// dropping the rest. Each call starts polling from a different offset into the slice (advanced
+/// by a simple per-call counter, no RNG needed) so no one branch is permanently polled first and
+/// starved out under steady-state activity.
+pub async fn select_any<Fut>(futures: Vec<Fut>) -> (usize, Fut::Output)
+where
+    Fut: Future,
+{
+    let len = futures.len();
+    assert!(len > 0, "select_any requires at least one future");
+    let offset = SELECT_ANY_OFFSET.with(|counter| {
+        let offset = counter.get();
+        counter.set((offset + 1) % len);
+        offset
+    });
+    SelectAny {
+        futures: futures.into_iter().map(Box::pin).collect(),
+        offset,
+    }
+    .await
+}
+
+struct SelectAny<Fut: Future> {
+    futures: Vec<Pin<Box<Fut>>>,
+    offset: usize,
+}
+impl<Fut: Future> Future for SelectAny<Fut> {
+    type Output = (usize, Fut::Output);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let len = self.futures.len();
+        for i in 0..len {
+            let index = (self.offset + i) % len;
+            if let Poll::Ready(value) = self.futures[index].as_mut().poll(cx) {
+                return Poll::Ready((index, value));
+            }
+        }
+        Poll::Pending
+    }
+}
+
 /// The business logic will wait on either a button press or a timer expiration.  This enum indicates which one completed first.
 #[derive(Debug, PartialEq)]
 enum TimerOrButton {
@@ -160,16 +235,64 @@ pub trait AsyncTimer {
     fn wait_expired(&self) -> impl Future<Output = TimerEvent>;
 }
 
+/// A periodic tick built on top of a `Repeated`-mode `AsyncTimer`, so a caller can just
+/// `interval.tick().await` in a loop instead of resetting the timer by hand each time.
+pub struct Interval<T: AsyncTimer> {
+    timer: T,
+}
+impl<T: AsyncTimer> Interval<T> {
+    pub fn new(timer: T) -> Self {
+        Self { timer }
+    }
+    /// Resync the interval to a fresh period starting now.
+    pub fn reset(&mut self) {
+        self.timer.reset();
+    }
+    /// Resolves once per period.
+    fn tick(&self) -> impl Future<Output = TimerEvent> + use<'_, T> {
+        self.timer.wait_expired()
+    }
+}
+
+/// Extension trait adding a `.timeout_after(timer)` combinator to any future, so racing work
+/// against an `AsyncTimer` no longer requires hand-building a `wait_for_one_to_complete` pair.
+pub trait TimeoutExt: Future + Sized {
+    /// Waits for this future to complete, returning `Err(())` if `timer` expires first.
+    fn timeout_after<T>(self, timer: &T) -> impl Future<Output = Result<Self::Output, ()>>
+    where
+        T: AsyncTimer;
+}
+impl<Fut> TimeoutExt for Fut
+where
+    Fut: Future,
+{
+    fn timeout_after<T>(self, timer: &T) -> impl Future<Output = Result<Self::Output, ()>>
+    where
+        T: AsyncTimer,
+    {
+        async move {
+            match wait_for_one_to_complete(self, timer.wait_expired()).await {
+                FirstOrSecond::First(value) => Ok(value),
+                FirstOrSecond::Second(_) => Err(()),
+            }
+        }
+    }
+}
+
+// Stays on the blanket `yield_control`/`wake_children` sweep rather than an `EventHandle`:
+// unlike a button press or a voltage read, expiry isn't a discrete state change a caller
+// can name ahead of time, it's just "time passed, go check" - any frame tick is a candidate,
+// so there's no more precise event to park on here.
 struct PollingAsyncTimer<T>
 where
-    T: Timer,
+    T: sync::Timer,
 {
     timer: T,
     blocker: FrameBlocker,
 }
 impl<T> AsyncTimer for PollingAsyncTimer<T>
 where
-    T: Timer,
+    T: sync::Timer,
 {
     fn reset(&mut self) {
         self.timer.reset();
@@ -190,6 +313,11 @@ where
 {
     io: I,
     blocker: FrameBlocker,
+    /// Woken by whichever caller knows the button just changed state, so the button
+    /// waiters below aren't dragged along by an unrelated `wake_children` sweep.
+    button_event: EventHandle,
+    /// Woken by whichever caller knows the voltage reading just changed.
+    voltage_event: EventHandle,
 }
 impl<I> IO for PollingAsyncIO<I>
 where
@@ -212,7 +340,7 @@ where
     fn wait_until_button_pressed(&mut self) -> impl Future<Output = ButtonEvent> {
         async {
             while !self.io.button_pressed() {
-                self.blocker.yield_control().await;
+                self.blocker.wait_for_event(&self.button_event).await;
             }
             ButtonEvent {}
         }
@@ -220,7 +348,7 @@ where
     fn wait_for_released(&self) -> impl Future<Output = ButtonEvent> {
         async {
             while !self.io.button_released() {
-                self.blocker.yield_control().await;
+                self.blocker.wait_for_event(&self.button_event).await;
             }
             ButtonEvent {}
         }
@@ -229,8 +357,96 @@ where
     fn wait_until_voltage_is(&self, value: bool) -> impl Future<Output = ()> {
         async move {
             while self.io.read_voltage() != value {
-                self.blocker.yield_control().await;
+                self.blocker.wait_for_event(&self.voltage_event).await;
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    /// A future that returns `Pending` (and re-wakes itself) a fixed number of times before
+    /// resolving, so combinator tests can prove a branch is actually polled across multiple
+    /// frames rather than just checking the outcome of already-`Ready` futures.
+    struct CountdownFuture(u32);
+    impl Future for CountdownFuture {
+        type Output = u32;
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            if self.0 == 0 {
+                Poll::Ready(0)
+            } else {
+                self.0 -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn wait_for_all_to_complete_returns_both_outputs() {
+        let (a, b) = block_on(wait_for_all_to_complete(CountdownFuture(3), async { "two" }));
+        assert_eq!(a, 0);
+        assert_eq!(b, "two");
+    }
+
+    #[test]
+    fn wait_for_all_collects_outputs_in_order() {
+        let result = block_on(wait_for_all(vec![
+            futures::future::ready(1),
+            futures::future::ready(2),
+            futures::future::ready(3),
+        ]));
+        assert_eq!(result, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn select_any_returns_whichever_future_finishes_first() {
+        let (index, value) =
+            block_on(select_any(vec![
+                CountdownFuture(5),
+                CountdownFuture(0),
+                CountdownFuture(5),
+            ]));
+        assert_eq!(index, 1);
+        assert_eq!(value, 0);
+    }
+
+    #[test]
+    fn select_any_rotates_the_starting_poll_order() {
+        let ready_futures = || {
+            vec![
+                futures::future::ready(()),
+                futures::future::ready(()),
+                futures::future::ready(()),
+            ]
+        };
+        let (first_index, _) = block_on(select_any(ready_futures()));
+        let (second_index, _) = block_on(select_any(ready_futures()));
+        assert_ne!(
+            first_index, second_index,
+            "back-to-back calls should not both favor the same branch"
+        );
+    }
+
+    #[test]
+    fn try_wait_all_short_circuits_on_first_error() {
+        let result: Result<Vec<i32>, &str> = block_on(try_wait_all(vec![
+            futures::future::ready(Ok(1)),
+            futures::future::ready(Err("boom")),
+            futures::future::ready(Ok(3)),
+        ]));
+        assert_eq!(result, Err("boom"));
+    }
+
+    #[test]
+    fn try_wait_all_collects_all_ok_values() {
+        let result: Result<Vec<i32>, &str> = block_on(try_wait_all(vec![
+            futures::future::ready(Ok(1)),
+            futures::future::ready(Ok(2)),
+        ]));
+        assert_eq!(result, Ok(vec![1, 2]));
+    }
+}
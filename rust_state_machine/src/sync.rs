@@ -1,4 +1,5 @@
-use std::time::Instant;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
 
 use crate::{Light, IO};
 
@@ -6,23 +7,84 @@ pub trait Timer {
     fn reset(&mut self);
     fn expired(&self) -> bool;
 }
+
+/// Whether a `Timer` fires once and stays expired, or auto-rearms for another period every
+/// time it is observed to have expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerMode {
+    SingleShot,
+    Repeated,
+}
+
 pub trait TimerFactory<T> {
-    fn new_timer(&self, timeout: f64) -> T
+    fn new_timer(&self, timeout: f64, mode: TimerMode) -> T
     where
         T: Timer;
 }
 
-struct SysTimer {
-    start: Instant,
+/// A source of the current instant, so `SysTimer` can be tested against a `MockClock` instead
+/// of `Instant::now()`.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// The real wall-clock `Clock`, backed by `Instant::now()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SysClock;
+impl Clock for SysClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SysTimer<C: Clock> {
+    clock: C,
+    start: Cell<Instant>,
     timeout: f64,
+    mode: TimerMode,
 }
-impl Timer for SysTimer {
+impl<C: Clock + Clone> SysTimer<C> {
+    pub fn new(clock: C, timeout: f64, mode: TimerMode) -> Self {
+        let start = Cell::new(clock.now());
+        Self {
+            clock,
+            start,
+            timeout,
+            mode,
+        }
+    }
+}
+impl<C: Clock> Timer for SysTimer<C> {
     fn reset(&mut self) {
-        self.start = Instant::now();
+        self.start.set(self.clock.now());
     }
     fn expired(&self) -> bool {
-        let diff = Instant::now() - self.start;
-        diff.as_secs_f64() > self.timeout
+        let diff = self.clock.now() - self.start.get();
+        let expired = diff.as_secs_f64() > self.timeout;
+        // Re-arms from the prior deadline, not `clock.now()`, so a late poll doesn't push back
+        // every later deadline. (`WheelTimer::expired` instead re-arms from the tick at which
+        // the expiry is observed.)
+        if expired && self.mode == TimerMode::Repeated {
+            self.start
+                .set(self.start.get() + Duration::from_secs_f64(self.timeout));
+        }
+        expired
+    }
+}
+
+/// A `TimerFactory` that hands out `SysTimer`s sharing this factory's `Clock`.
+pub struct SysTimerFactory<C: Clock + Clone> {
+    clock: C,
+}
+impl<C: Clock + Clone> SysTimerFactory<C> {
+    pub fn new(clock: C) -> Self {
+        Self { clock }
+    }
+}
+impl<C: Clock + Clone> TimerFactory<SysTimer<C>> for SysTimerFactory<C> {
+    fn new_timer(&self, timeout: f64, mode: TimerMode) -> SysTimer<C> {
+        SysTimer::new(self.clock.clone(), timeout, mode)
     }
 }
 
@@ -72,7 +134,7 @@ where
             States::NotPressed => {
                 if self.io.button_pressed() {
                     self.io.set_light(Light::On);
-                    self.state = States::BlinkOn(self.tf.new_timer(1.0));
+                    self.state = States::BlinkOn(self.tf.new_timer(1.0, TimerMode::SingleShot));
                     true
                 } else {
                     false
@@ -81,7 +143,7 @@ where
             States::BlinkOn(ref timer) => {
                 if timer.expired() {
                     self.io.set_light(Light::Off);
-                    self.state = States::BlinkOff(self.tf.new_timer(1.0));
+                    self.state = States::BlinkOff(self.tf.new_timer(1.0, TimerMode::SingleShot));
                     true
                 } else if self.io.button_released() {
                     self.state = States::ReleasedButton;
@@ -93,7 +155,7 @@ where
             States::BlinkOff(ref timer) => {
                 if timer.expired() {
                     self.io.set_light(Light::On);
-                    self.state = States::BlinkOn(self.tf.new_timer(1.0));
+                    self.state = States::BlinkOn(self.tf.new_timer(1.0, TimerMode::SingleShot));
                     true
                 } else if self.io.button_released() {
                     self.state = States::ReleasedButton;
@@ -118,29 +180,33 @@ pub mod tests {
     use super::*;
     use std::cell::RefCell;
     use std::rc::Rc;
+    use std::time::Duration;
 
-    #[derive(Clone, Default, Debug)]
-    pub struct MockTimer {
-        expired: Rc<RefCell<bool>>,
+    /// A `Clock` whose `now()` is a virtual instant that only moves when `advance` is called,
+    /// letting tests drive timer expiry with real duration arithmetic instead of a boolean flag.
+    #[derive(Debug, Clone)]
+    pub struct MockClock {
+        now: Rc<RefCell<Instant>>,
     }
-    impl MockTimer {
-        pub fn new(expired: Rc<RefCell<bool>>) -> Self {
-            Self { expired }
+    impl MockClock {
+        pub fn new() -> Self {
+            Self {
+                now: Rc::new(RefCell::new(Instant::now())),
+            }
         }
-    }
-    impl Timer for MockTimer {
-        fn expired(&self) -> bool {
-            *self.expired.borrow()
+        pub fn advance(&self, duration: Duration) {
+            let now = *self.now.borrow() + duration;
+            *self.now.borrow_mut() = now;
         }
-
-        fn reset(&mut self) {
-            self.expired.replace(false);
+    }
+    impl Default for MockClock {
+        fn default() -> Self {
+            Self::new()
         }
     }
-    impl TimerFactory<MockTimer> for MockTimer {
-        fn new_timer(&self, _timeout: f64) -> MockTimer {
-            *self.expired.borrow_mut() = false;
-            self.clone()
+    impl Clock for MockClock {
+        fn now(&self) -> Instant {
+            *self.now.borrow()
         }
     }
 
@@ -175,6 +241,32 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn repeated_timer_rearm_does_not_drift_with_late_polling() {
+        let clock = MockClock::new();
+        let timer = SysTimer::new(clock.clone(), 1.0, TimerMode::Repeated);
+
+        // Poll late, well after the 1.0s deadline, simulating a frame tick that was slow
+        // to come back around.
+        clock.advance(Duration::from_secs_f64(1.3));
+        assert!(timer.expired(), "should have expired by the 1.0s deadline");
+
+        // Had the re-arm anchored to `clock.now()` instead of the prior deadline, the 0.3s
+        // overrun would be tacked onto every future period (checked at the 2.0s mark below).
+        // Anchored to the deadline, only 0.3s of the fresh 1.0s period has elapsed.
+        assert!(
+            !timer.expired(),
+            "should not still read expired right after re-arming"
+        );
+
+        // The second period's boundary should still land at the original 2.0s mark, not at
+        // 2.3s (the late poll's time plus a full period).
+        clock.advance(Duration::from_secs_f64(0.69));
+        assert!(!timer.expired(), "second period should not have fired yet");
+        clock.advance(Duration::from_secs_f64(0.02));
+        assert!(timer.expired(), "second period should fire at the 2.0s mark");
+    }
+
     #[test]
     fn test_state_machine() {
         let button_pressed = Rc::new(RefCell::new(false));
@@ -185,14 +277,9 @@ pub mod tests {
             light: light.clone(),
             voltage: voltage.clone(),
         };
-        let expired = Rc::new(RefCell::new(false));
+        let clock = MockClock::new();
 
-        let mut behavior = StateMachineSync::new(
-            io,
-            MockTimer {
-                expired: expired.clone(),
-            },
-        );
+        let mut behavior = StateMachineSync::new(io, SysTimerFactory::new(clock.clone()));
 
         for _ in 0..100 {
             behavior.do_work();
@@ -215,7 +302,7 @@ pub mod tests {
             );
         }
 
-        *expired.borrow_mut() = true;
+        clock.advance(Duration::from_secs_f64(1.1));
 
         for _ in 0..100 {
             behavior.do_work();
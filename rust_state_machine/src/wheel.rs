@@ -0,0 +1,283 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::sync::{Timer, TimerFactory, TimerMode};
+
+/// A single scheduled timeout living in the wheel's slab.
+struct WheelEntry {
+    /// Absolute tick at which this entry should fire.
+    target_tick: u64,
+    /// How many ticks this entry's timeout spans, kept around so `reset` (and a `Repeated`
+    /// entry's auto-rearm) can reschedule it.
+    duration_ticks: u64,
+    expired: bool,
+    mode: TimerMode,
+}
+
+struct Wheel {
+    /// `num_slots` slots, each holding the slab tokens of the entries that currently land there.
+    slots: Vec<Vec<usize>>,
+    slab: Vec<Option<WheelEntry>>,
+    free_list: Vec<usize>,
+    current_tick: u64,
+    mask: usize,
+    tick_secs: f64,
+}
+impl Wheel {
+    fn schedule(&mut self, duration_ticks: u64, mode: TimerMode) -> usize {
+        let target_tick = self.current_tick + duration_ticks;
+        let token = self.alloc(WheelEntry {
+            target_tick,
+            duration_ticks,
+            expired: false,
+            mode,
+        });
+        let slot = (target_tick as usize) & self.mask;
+        self.slots[slot].push(token);
+        token
+    }
+    fn alloc(&mut self, entry: WheelEntry) -> usize {
+        if let Some(token) = self.free_list.pop() {
+            self.slab[token] = Some(entry);
+            token
+        } else {
+            self.slab.push(Some(entry));
+            self.slab.len() - 1
+        }
+    }
+    /// Remove an entry from the slab and from the slot it's currently sitting in, so a reused
+    /// token can't leave a stale entry stranded in that slot forever.
+    fn cancel(&mut self, token: usize) {
+        if let Some(entry) = self.slab[token].take() {
+            let slot = (entry.target_tick as usize) & self.mask;
+            self.slots[slot].retain(|&t| t != token);
+        }
+        self.free_list.push(token);
+    }
+    fn tick(&mut self) {
+        self.current_tick += 1;
+        let current_tick = self.current_tick;
+        let slot = (current_tick as usize) & self.mask;
+
+        // Take this slot's tokens so entries that are still further revolutions away can be
+        // put back without ever touching slots that didn't just become current.
+        let tokens = std::mem::take(&mut self.slots[slot]);
+        let mut remaining = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            if let Some(entry) = self.slab.get_mut(token).and_then(|e| e.as_mut()) {
+                if entry.target_tick == current_tick {
+                    entry.expired = true;
+                } else {
+                    // Landed in this slot but belongs to a later revolution of the wheel.
+                    remaining.push(token);
+                }
+            }
+        }
+        self.slots[slot] = remaining;
+    }
+}
+
+/// A hashed timing wheel `TimerFactory`: all timers it hands out are advanced together by a
+/// single `tick()` call instead of each one re-reading the clock on every `expired()` poll.
+/// `num_slots` must be a power of two.
+pub struct WheelTimerFactory {
+    wheel: Rc<RefCell<Wheel>>,
+}
+impl WheelTimerFactory {
+    pub fn new(num_slots: usize, tick_secs: f64) -> Self {
+        assert!(
+            num_slots.is_power_of_two(),
+            "num_slots must be a power of two"
+        );
+        Self {
+            wheel: Rc::new(RefCell::new(Wheel {
+                slots: vec![Vec::new(); num_slots],
+                slab: Vec::new(),
+                free_list: Vec::new(),
+                current_tick: 0,
+                mask: num_slots - 1,
+                tick_secs,
+            })),
+        }
+    }
+    /// Advance the wheel by one tick, marking as expired any timer whose target tick has
+    /// just arrived. This is the single per-frame sweep shared by every timer from this factory.
+    pub fn tick(&self) {
+        self.wheel.borrow_mut().tick();
+    }
+}
+impl TimerFactory<WheelTimer> for WheelTimerFactory {
+    fn new_timer(&self, timeout: f64, mode: TimerMode) -> WheelTimer {
+        let duration_ticks = {
+            let wheel = self.wheel.borrow();
+            ((timeout / wheel.tick_secs).ceil() as u64).max(1)
+        };
+        let token = self.wheel.borrow_mut().schedule(duration_ticks, mode);
+        WheelTimer {
+            wheel: self.wheel.clone(),
+            token,
+        }
+    }
+}
+
+/// A lightweight handle into a `WheelTimerFactory`'s wheel: a shared reference to the wheel
+/// plus a slab token. The handle does not hold the target tick; that lives in the slab entry.
+pub struct WheelTimer {
+    wheel: Rc<RefCell<Wheel>>,
+    token: usize,
+}
+impl Timer for WheelTimer {
+    fn reset(&mut self) {
+        let mut wheel = self.wheel.borrow_mut();
+        let entry = wheel.slab[self.token]
+            .as_ref()
+            .expect("WheelTimer's slab entry was already cancelled");
+        let (duration_ticks, mode) = (entry.duration_ticks, entry.mode);
+        wheel.cancel(self.token);
+        self.token = wheel.schedule(duration_ticks, mode);
+    }
+    fn expired(&self) -> bool {
+        let mut wheel = self.wheel.borrow_mut();
+        let current_tick = wheel.current_tick;
+        let Some(entry) = wheel.slab.get(self.token).and_then(|e| e.as_ref()) else {
+            return false;
+        };
+        if !entry.expired {
+            return false;
+        }
+        // A Repeated entry re-arms itself the moment it is observed to have expired, so a
+        // single WheelTimer can drive a periodic loop without anyone calling reset() by hand.
+        if entry.mode == TimerMode::Repeated {
+            let duration_ticks = entry.duration_ticks;
+            let new_target = current_tick + duration_ticks;
+            let entry = wheel.slab[self.token].as_mut().expect("checked above");
+            entry.expired = false;
+            entry.target_tick = new_target;
+            let new_slot = (new_target as usize) & wheel.mask;
+            wheel.slots[new_slot].push(self.token);
+        }
+        true
+    }
+}
+impl Drop for WheelTimer {
+    /// A dropped timer would otherwise leak its slab slot forever.
+    fn drop(&mut self) {
+        self.wheel.borrow_mut().cancel(self.token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fires_on_the_correct_tick() {
+        let factory = WheelTimerFactory::new(8, 0.1);
+        let timer = factory.new_timer(0.25, TimerMode::SingleShot); // ceil(0.25 / 0.1) = 3 ticks
+
+        for _ in 0..2 {
+            factory.tick();
+            assert!(!timer.expired());
+        }
+        factory.tick();
+        assert!(timer.expired());
+    }
+
+    #[test]
+    fn wrap_around_does_not_fire_early() {
+        // 4 slots means a timer more than 4 ticks out lands in the same slot as a sooner one.
+        let factory = WheelTimerFactory::new(4, 0.1);
+        let soon = factory.new_timer(0.1, TimerMode::SingleShot); // 1 tick, slot 1
+        let later = factory.new_timer(0.5, TimerMode::SingleShot); // 5 ticks, also slot 1 (5 & 3 == 1)
+
+        factory.tick();
+        assert!(soon.expired());
+        assert!(!later.expired());
+
+        for _ in 0..3 {
+            factory.tick();
+            assert!(!later.expired());
+        }
+        factory.tick();
+        assert!(later.expired());
+    }
+
+    #[test]
+    fn reset_reschedules_from_now() {
+        let factory = WheelTimerFactory::new(8, 0.1);
+        let mut timer = factory.new_timer(0.2, TimerMode::SingleShot); // 2 ticks
+
+        factory.tick();
+        timer.reset();
+        // Had the reset not rescheduled, this tick would have fired the original target.
+        factory.tick();
+        assert!(!timer.expired());
+        factory.tick();
+        assert!(timer.expired());
+    }
+
+    #[test]
+    fn cancel_on_reset_does_not_leak_a_stale_fire() {
+        let factory = WheelTimerFactory::new(8, 0.1);
+        let mut timer = factory.new_timer(0.1, TimerMode::SingleShot); // 1 tick
+        timer.reset(); // cancels the original 1-tick entry, reschedules another 1-tick entry
+        factory.tick();
+        assert!(timer.expired());
+    }
+
+    #[test]
+    fn repeated_reset_does_not_strand_tokens_in_old_slots() {
+        let factory = WheelTimerFactory::new(8, 0.1);
+        let mut timer = factory.new_timer(0.7, TimerMode::SingleShot); // 7 ticks
+
+        for _ in 0..50 {
+            factory.tick();
+            timer.reset();
+        }
+        for _ in 0..200 {
+            factory.tick();
+        }
+
+        let wheel = factory.wheel.borrow();
+        let total_slotted: usize = wheel.slots.iter().map(|slot| slot.len()).sum();
+        assert!(
+            total_slotted <= 1,
+            "only the timer's own still-pending entry should remain slotted, found {total_slotted}"
+        );
+    }
+
+    #[test]
+    fn dropping_a_timer_frees_its_slab_slot() {
+        let factory = WheelTimerFactory::new(8, 0.1);
+        let slab_len_before = factory.wheel.borrow().slab.len();
+
+        let timer = factory.new_timer(0.5, TimerMode::SingleShot); // never fired or reset
+        assert_eq!(factory.wheel.borrow().slab.len(), slab_len_before + 1);
+        drop(timer);
+
+        // The freed slot is reused instead of the slab growing further.
+        let _reused = factory.new_timer(0.5, TimerMode::SingleShot);
+        assert_eq!(factory.wheel.borrow().slab.len(), slab_len_before + 1);
+    }
+
+    #[test]
+    fn repeated_timer_auto_rearms_on_observed_expiry() {
+        let factory = WheelTimerFactory::new(8, 0.1);
+        let timer = factory.new_timer(0.2, TimerMode::Repeated); // 2 ticks
+
+        factory.tick();
+        assert!(!timer.expired());
+        factory.tick();
+        assert!(timer.expired(), "should fire after the first period");
+        // Observing the expiry above should have re-armed it for another 2 ticks.
+        assert!(
+            !timer.expired(),
+            "should not still read expired right after re-arming"
+        );
+
+        factory.tick();
+        assert!(!timer.expired());
+        factory.tick();
+        assert!(timer.expired(), "should fire again after the second period");
+    }
+}